@@ -1,4 +1,6 @@
 use crate::read::AiffData;
+use rustfft::{FftPlanner, num_complex::Complex};
+use std::f32::consts::PI;
 
 pub fn calculate_bpm_energy_manual_threshold(aiff_data: &AiffData, window_size: usize, threshold: f32) -> Result<f32, Box<dyn std::error::Error>> {
     /* Calculates the BPM of the song using a set energy calculation and threshold.
@@ -100,3 +102,78 @@ pub fn calculate_bpm_energy_dynamic_threshold(aiff_data: &AiffData, window_size:
 
     Ok(60.0 / avg_diff)
 }
+
+pub fn calculate_bpm_spectral_flux(aiff_data: &AiffData, frame_size: usize, hop_size: usize) -> Result<f32, Box<dyn std::error::Error>> {
+    /* Estimates BPM from a spectral-flux onset envelope, which tracks percussive onsets far more
+    reliably than raw energy (sustained loud passages no longer masquerade as beats). Algorithm:
+    1. Average audio channels together if stereo.
+    2. Hann-window each frame and take its FFT magnitude spectrum.
+    3. Onset envelope = spectral flux, the sum of positive magnitude increases across bins.
+    4. Autocorrelate the envelope over lags spanning 60-200 BPM and pick the strongest lag.
+    5. Convert that lag back to BPM.
+    */
+
+    let reference_audio_data: Vec<f32> = if aiff_data.num_channels == 1 {
+        aiff_data.left_channel_audio.clone()
+    } else {
+        aiff_data.left_channel_audio
+            .iter()
+            .zip(aiff_data.right_channel_audio.iter())
+            .map(|(&left, &right)| (left + right) / 2.0)
+            .collect()
+    };
+
+    if reference_audio_data.len() < frame_size {
+        return Err("Audio is shorter than a single analysis frame.".into());
+    }
+
+    let hann: Vec<f32> = (0..frame_size)  // precompute the Hann window once and reuse it per frame
+        .map(|i| 0.5 * (1.0 - (2.0 * PI * i as f32 / (frame_size as f32 - 1.0)).cos()))
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(frame_size);
+
+    let mut flux: Vec<f32> = Vec::new();
+    let mut prev_magnitude: Vec<f32> = vec![0.0; frame_size];
+    let mut frame_start = 0;
+    while frame_start + frame_size <= reference_audio_data.len() {
+        let mut buffer: Vec<Complex<f32>> = (0..frame_size)
+            .map(|i| Complex::new(reference_audio_data[frame_start + i] * hann[i], 0.0))
+            .collect();
+        fft.process(&mut buffer);
+
+        let magnitude: Vec<f32> = buffer.iter().map(|c| c.norm()).collect();  // |X_t[k]|
+        let sum = magnitude.iter()
+            .zip(prev_magnitude.iter())
+            .map(|(&cur, &prev)| (cur - prev).max(0.0))  // only positive increases contribute to flux
+            .sum::<f32>();
+        flux.push(sum);
+
+        prev_magnitude = magnitude;
+        frame_start += hop_size;
+    }
+
+    if flux.len() < 2 {
+        return Err("Not enough frames to estimate tempo.".into());
+    }
+
+    let env_rate = aiff_data.sample_rate_hz as f32 / hop_size as f32;  // onset envelope sample rate, in frames per second
+
+    let min_lag = (60.0 * env_rate / 200.0).round() as usize;  // 200 BPM -> shortest lag
+    let max_lag = (60.0 * env_rate / 60.0).round() as usize;  // 60 BPM -> longest lag
+
+    let mut best_lag = min_lag.max(1);
+    let mut best_score = f32::MIN;
+    for lag in min_lag.max(1)..=max_lag.min(flux.len() - 1) {
+        let score: f32 = (0..flux.len() - lag)
+            .map(|j| flux[j] * flux[j + lag])
+            .sum();
+        if score > best_score {
+            best_score = score;
+            best_lag = lag;
+        }
+    }
+
+    Ok(60.0 * env_rate / best_lag as f32)
+}
@@ -1,10 +1,33 @@
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
 use byteorder::{BigEndian, ReadBytesExt};
 use std::time::Instant;
 
 const MAX_F32_SIZE: f32 = 32768.0;
 
+#[derive(Clone)]
+pub struct Marker {
+    pub id: u16,
+    pub position: u32,  // sample frame the marker points at
+    pub name: String,
+}
+
+#[derive(Clone)]
+pub struct Loop {
+    pub play_mode: i16,  // 0 = no looping, 1 = forward, 2 = forward/backward
+    pub begin_marker: u16,  // marker id where the loop begins
+    pub end_marker: u16,  // marker id where the loop ends
+}
+
+#[derive(Clone)]
+pub struct Instrument {
+    pub base_note: u8,  // MIDI note the sample is pitched at
+    pub detune: i8,  // fine tuning in cents, -50..50
+    pub gain: i16,  // playback gain in decibels
+    pub sustain_loop: Loop,
+    pub release_loop: Loop,
+}
+
 pub struct AiffData {
     pub file_size_bytes: u32,
     pub num_channels: u16,
@@ -13,15 +36,22 @@ pub struct AiffData {
     pub sample_rate_hz: u32,
     pub track_name: String,
     pub track_length_s: u16,
-    pub sound_offset_bytes: u16,
-    pub sound_block_size_bytes: u16,
+    pub sound_offset_bytes: u32,
+    pub sound_block_size_bytes: u32,
     pub left_channel_audio: Vec<f32>,  // all audio data is stored as an f32, where all values are scaled from -1 to 1
     pub right_channel_audio: Vec<f32>,
     pub interleaved_audio: Vec<f32>,
+    pub markers: Vec<Marker>,
+    pub instrument: Option<Instrument>,
+    pub comments: Vec<String>,
 }
 
 pub fn read_aiff(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>> {
-    /* Reads the .aiff file and prints key information about it. */
+    /* Reads the .aiff file and prints key information about it. Rather than assuming a fixed
+    chunk order, we validate the FORM/AIFF container then loop over the remaining chunks,
+    routing each 4-byte chunk ID to a handler and seeking past anything we don't understand.
+    This keeps the reader order-independent and forward-compatible with files that carry extra
+    chunks. */
 
     let start_time = Instant::now();
 
@@ -43,49 +73,102 @@ pub fn read_aiff(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>>
         return Err("Not a valid AIFF file".into());
     }
 
-    let mut name_chunk = [0u8; 4];  // 4 bytes after the AIFF identifier is the NAME chunk (which is optional)
+    // Everything we accumulate while walking the chunks. The COMM chunk fills in the format
+    // fields and SSND hands us the raw sample bytes; any chunk we don't recognize is skipped.
+    let mut num_channels: u16 = 0;
+    let mut num_sample_frames: u32 = 0;
+    let mut bit_depth: u16 = 0;
+    let mut sample_rate_hz: u32 = 0;
     let mut track_name = String::new();
-    file.read_exact(&mut name_chunk)?;
-    if &name_chunk == b"NAME" {
+    let mut ssnd_offset: u32 = 0;
+    let mut ssnd_block_size: u32 = 0;
+    let mut audio_data: Vec<u8> = Vec::new();
+    let mut markers: Vec<Marker> = Vec::new();
+    let mut instrument: Option<Instrument> = None;
+    let mut comments: Vec<String> = Vec::new();
+
+    loop {
+        let mut chunk_id = [0u8; 4];  // every chunk is a 4-byte ID followed by a big-endian u32 size
+        match file.read_exact(&mut chunk_id) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == ErrorKind::UnexpectedEof => break,  // clean end of the FORM container
+            Err(err) => return Err(err.into()),
+        }
         let chunk_size = file.read_u32::<BigEndian>()?;
-        let mut name_data = vec![0u8; chunk_size as usize];
-        file.read_exact(&mut name_data)?;
-        track_name = String::from_utf8_lossy(&name_data).to_string();
-    } else {
-        file.seek(SeekFrom::Current(-4))?;  // go back 4 bytes if this isn't the name chunk
-    }
+        let body_start = file.stream_position()?;  // remember where the body starts so we can realign after each handler
 
-    let mut comm_chunk = [0u8; 4];  // next 4 bytes is the COMM chunk
-    file.read_exact(&mut comm_chunk)?;
-    if &comm_chunk != b"COMM" {
-        return Err("COMM chunk not found".into());
-    }
+        match &chunk_id {
+            b"COMM" => {
+                num_channels = file.read_u16::<BigEndian>()?;  // 2 bytes for channel count
+                num_sample_frames = file.read_u32::<BigEndian>()?;  // 4 bytes for number of frames
+                bit_depth = file.read_u16::<BigEndian>()?;  // 2 bytes for bit depth
+                sample_rate_hz = read_extended_float(&mut file)? as u32;  // 10 bytes for sample rate
+            }
+            b"SSND" => {
+                ssnd_offset = file.read_u32::<BigEndian>()?;  // ssnd offset 4 bytes
+                ssnd_block_size = file.read_u32::<BigEndian>()?;  // block size 4 bytes
+                file.seek(SeekFrom::Current(ssnd_offset as i64))?;  // respect the ssnd offset
+                let data_len = chunk_size as usize - 8 - ssnd_offset as usize;  // chunk size covers offset + block size + samples
+                audio_data = vec![0u8; data_len];
+                file.read_exact(&mut audio_data)?;  // the raw big-endian PCM samples
+            }
+            b"NAME" => {
+                let mut name_data = vec![0u8; chunk_size as usize];
+                file.read_exact(&mut name_data)?;
+                track_name = String::from_utf8_lossy(&name_data).to_string();
+            }
+            b"MARK" => {
+                let num_markers = file.read_u16::<BigEndian>()?;  // numbered markers, each with a sample position and Pascal-string name
+                for _ in 0..num_markers {
+                    let id = file.read_u16::<BigEndian>()?;
+                    let position = file.read_u32::<BigEndian>()?;
+                    let name = read_pascal_string(&mut file)?;
+                    markers.push(Marker { id, position, name });
+                }
+            }
+            b"INST" => {
+                let base_note = file.read_u8()?;
+                let detune = file.read_i8()?;
+                let _low_note = file.read_u8()?;
+                let _high_note = file.read_u8()?;
+                let _low_velocity = file.read_u8()?;
+                let _high_velocity = file.read_u8()?;
+                let gain = file.read_i16::<BigEndian>()?;
+                let sustain_loop = read_loop(&mut file)?;  // each loop references marker ids for its begin/end points
+                let release_loop = read_loop(&mut file)?;
+                instrument = Some(Instrument { base_note, detune, gain, sustain_loop, release_loop });
+            }
+            b"COMT" => {
+                let num_comments = file.read_u16::<BigEndian>()?;
+                for _ in 0..num_comments {
+                    let _timestamp = file.read_u32::<BigEndian>()?;
+                    let _marker_id = file.read_u16::<BigEndian>()?;
+                    let count = file.read_u16::<BigEndian>()? as usize;  // text length, padded to even
+                    let mut text = vec![0u8; count];
+                    file.read_exact(&mut text)?;
+                    comments.push(String::from_utf8_lossy(&text).to_string());
+                    if count % 2 == 1 {
+                        file.seek(SeekFrom::Current(1))?;  // comment text is padded to an even byte boundary
+                    }
+                }
+            }
+            // AUTH/ANNO/APPL and any other chunk are skipped cleanly via the realign below.
+            _ => {}
+        }
 
-    let comm_chunk_size = file.read_u32::<BigEndian>()?;  // after the COMM chunk, verify that the size is 18 (by reading next 4 bytes) 
-    if comm_chunk_size != 18 {
-        return Err("Unexpected COMM chunk size".into());
+        // Realign to the declared end of the chunk regardless of how much the handler consumed,
+        // then honor the pad byte that keeps every chunk on an even boundary.
+        file.seek(SeekFrom::Start(body_start + chunk_size as u64))?;
+        if chunk_size % 2 == 1 {
+            file.seek(SeekFrom::Current(1))?;
+        }
     }
 
-    let num_channels = file.read_u16::<BigEndian>()?;  // 2 bytes for channel count
-    let num_sample_frames = file.read_u32::<BigEndian>()?;  // 4 bytes for number of frames
-    let bit_depth = file.read_u16::<BigEndian>()?;  // 2 bytes for bit depth
-    let sample_rate_hz = read_extended_float(&mut file)? as u32;  // 10 bytes for sample rate
-    let track_length_s = (num_sample_frames / sample_rate_hz) as u16;
-
-    let mut ssnd_chunk = [0u8; 4];  // 4 bytes after this is SSND chunk
-    file.read_exact(&mut ssnd_chunk)?;
-    if &ssnd_chunk != b"SSND" {
-        return Err("SSND chunk not found".into());
+    if bit_depth == 0 {
+        return Err("COMM chunk not found".into());
     }
 
-    let _ssnd_chunk_size = file.read_u32::<BigEndian>()?;  // chunk size 4 bytes
-    let ssnd_offset = file.read_u16::<BigEndian>()?;  // ssnd offset 4 bytes
-    let ssnd_block_size = file.read_u16::<BigEndian>()?;  // block size 4 bytes
-
-    file.seek(SeekFrom::Current(ssnd_offset as i64))?;  // respect the ssnd offset
-
-    let mut audio_data = Vec::new();
-    file.read_to_end(&mut audio_data)?;  // reading all of the audio data into a buffer to the end of the file
+    let track_length_s = (num_sample_frames / sample_rate_hz) as u16;
 
     let mut left_channel_audio = Vec::new();
     let mut right_channel_audio = Vec::new();
@@ -94,7 +177,8 @@ pub fn read_aiff(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>>
 
     if num_channels == 1 {
         left_channel_audio = audio_data.chunks(bytes_per_sample)
-            .map(|chunk| (i16::from_be_bytes([chunk[0], chunk[1]]) as f32) / MAX_F32_SIZE)
+            .filter(|chunk| chunk.len() == bytes_per_sample)
+            .map(unpack_sample)
             .collect();
 
         right_channel_audio = left_channel_audio.clone();
@@ -102,11 +186,8 @@ pub fn read_aiff(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>>
     } else if num_channels == 2 {
         for chunk in audio_data.chunks(bytes_per_sample * 2) {
             if chunk.len() == bytes_per_sample * 2 {
-                let left_sample_int = i16::from_be_bytes([chunk[0], chunk[1]]);  // first load as an i16
-                let right_sample_int = i16::from_be_bytes([chunk[2], chunk[3]]);
-
-                let left_sample_f32 = (left_sample_int as f32) / MAX_F32_SIZE;  // convert to f32 for better sampling and audio inference
-                let right_sample_f32 = (right_sample_int as f32) / MAX_F32_SIZE;
+                let left_sample_f32 = unpack_sample(&chunk[..bytes_per_sample]);  // convert to f32 for better sampling and audio inference
+                let right_sample_f32 = unpack_sample(&chunk[bytes_per_sample..]);
 
                 left_channel_audio.push(left_sample_f32);
                 right_channel_audio.push(right_sample_f32);
@@ -139,9 +220,50 @@ pub fn read_aiff(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>>
         left_channel_audio,
         right_channel_audio,
         interleaved_audio,
+        markers,
+        instrument,
+        comments,
     })
 }
 
+fn read_loop(file: &mut File) -> Result<Loop, Box<dyn std::error::Error>> {
+    /* Reads a 6-byte AIFF loop record: a play mode followed by the begin/end marker ids. */
+    let play_mode = file.read_i16::<BigEndian>()?;
+    let begin_marker = file.read_u16::<BigEndian>()?;
+    let end_marker = file.read_u16::<BigEndian>()?;
+    Ok(Loop { play_mode, begin_marker, end_marker })
+}
+
+fn read_pascal_string(file: &mut File) -> Result<String, Box<dyn std::error::Error>> {
+    /* Reads a Pascal-style string: a single length byte followed by that many characters. The total
+    length (count byte plus characters) is padded to an even number of bytes, so we skip a trailing
+    pad byte when the count is even. */
+    let count = file.read_u8()? as usize;
+    let mut data = vec![0u8; count];
+    file.read_exact(&mut data)?;
+    if (count + 1) % 2 == 1 {
+        file.seek(SeekFrom::Current(1))?;  // 1 (length byte) + count characters must be even
+    }
+    Ok(String::from_utf8_lossy(&data).to_string())
+}
+
+fn unpack_sample(bytes: &[u8]) -> f32 {
+    /* Converts one big-endian PCM sample into the -1.0..1.0 f32 range based on how many bytes it
+    occupies. AIFF integer PCM is signed, so we sign-extend the 8/24-bit cases by hand and divide
+    by the magnitude of that depth's most-negative value. Anything unexpected decodes to silence. */
+    match bytes.len() {
+        1 => (bytes[0] as i8 as f32) / 128.0,  // 8-bit signed
+        2 => (i16::from_be_bytes([bytes[0], bytes[1]]) as f32) / MAX_F32_SIZE,  // 16-bit signed
+        3 => {
+            let raw = ((bytes[0] as i32) << 16) | ((bytes[1] as i32) << 8) | (bytes[2] as i32);
+            let sample = (raw << 8) >> 8;  // shift up then arithmetic-shift down to sign-extend the 24th bit
+            (sample as f32) / 8388608.0
+        }
+        4 => (i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32) / 2147483648.0,  // 32-bit signed
+        _ => 0.0,
+    }
+}
+
 fn read_extended_float(file: &mut File) -> Result<f64, Box<dyn std::error::Error>> {
     /* aiff files use 80-bit (10-byte) floating point to store the sample rate. this isn't supported
     natively by rust. Thus, we have to parse it ourselves. We pass in the file, assuming the
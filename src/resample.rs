@@ -0,0 +1,99 @@
+use std::f32::consts::PI;
+use crate::read::AiffData;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterpolationMode {
+    Nearest,
+    Linear,
+    Cosine,
+    Cubic,
+}
+
+pub fn resample(aiff_data: &AiffData, target_rate_hz: u32, mode: InterpolationMode) -> AiffData {
+    /* Resamples the audio to a new sample rate by interpolating each channel independently. For
+    every output index i we map back to a source position p = i * (src_rate / dst_rate) and read the
+    buffer with the selected interpolation mode. The channel buffers, interleaved audio, sample rate,
+    frame count and track length are all rebuilt to reflect the new rate. */
+
+    let ratio = aiff_data.sample_rate_hz as f32 / target_rate_hz as f32;  // source samples consumed per output sample
+    let out_frames = (aiff_data.left_channel_audio.len() as f32 / ratio).round() as usize;
+
+    let left_channel_audio = interpolate_channel(&aiff_data.left_channel_audio, ratio, out_frames, mode);
+    let right_channel_audio = interpolate_channel(&aiff_data.right_channel_audio, ratio, out_frames, mode);
+
+    let mut interleaved_audio = Vec::new();
+    if aiff_data.num_channels == 1 {
+        interleaved_audio = left_channel_audio.clone();
+    } else {
+        for i in 0..out_frames {
+            interleaved_audio.push(left_channel_audio[i]);
+            interleaved_audio.push(right_channel_audio[i]);
+        }
+    }
+
+    let num_sample_frames = out_frames as u32;
+    let track_length_s = (num_sample_frames / target_rate_hz) as u16;
+
+    AiffData {
+        file_size_bytes: aiff_data.file_size_bytes,
+        num_channels: aiff_data.num_channels,
+        num_sample_frames,
+        bit_depth: aiff_data.bit_depth,
+        sample_rate_hz: target_rate_hz,
+        track_name: aiff_data.track_name.clone(),
+        track_length_s,
+        sound_offset_bytes: aiff_data.sound_offset_bytes,
+        sound_block_size_bytes: aiff_data.sound_block_size_bytes,
+        left_channel_audio,
+        right_channel_audio,
+        interleaved_audio,
+        markers: aiff_data.markers.clone(),
+        instrument: aiff_data.instrument.clone(),
+        comments: aiff_data.comments.clone(),
+    }
+}
+
+fn interpolate_channel(samples: &[f32], ratio: f32, out_frames: usize, mode: InterpolationMode) -> Vec<f32> {
+    /* Produces out_frames interpolated samples from the source channel. Out-of-range taps are
+    clamped to the ends of the buffer so the edges stay well-defined. */
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out = Vec::with_capacity(out_frames);
+    for i in 0..out_frames {
+        let p = i as f32 * ratio;
+        let n = p.floor() as isize;
+        let t = p - n as f32;
+
+        let sample = match mode {
+            InterpolationMode::Nearest => tap(samples, p.round() as isize),
+            InterpolationMode::Linear => {
+                tap(samples, n) * (1.0 - t) + tap(samples, n + 1) * t
+            }
+            InterpolationMode::Cosine => {
+                let t2 = (1.0 - (t * PI).cos()) / 2.0;
+                tap(samples, n) * (1.0 - t2) + tap(samples, n + 1) * t2
+            }
+            InterpolationMode::Cubic => {
+                let x0 = tap(samples, n - 1);
+                let x1 = tap(samples, n);
+                let x2 = tap(samples, n + 1);
+                let x3 = tap(samples, n + 2);
+                let a0 = x3 - x2 - x0 + x1;
+                let a1 = x0 - x1 - a0;
+                let a2 = x2 - x0;
+                let a3 = x1;
+                ((a0 * t + a1) * t + a2) * t + a3
+            }
+        };
+        out.push(sample);
+    }
+    out
+}
+
+fn tap(samples: &[f32], index: isize) -> f32 {
+    /* Reads a sample, clamping the index to the valid range of the buffer. */
+    let clamped = index.clamp(0, samples.len() as isize - 1) as usize;
+    samples[clamped]
+}
@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use crate::read::{read_aiff, AiffData};
+use crate::write::write_aiff;
+
+const MAX_F32_SIZE: f32 = 32768.0;
+
+pub fn read_wav(filepath: &str) -> Result<AiffData, Box<dyn std::error::Error>> {
+    /* Reads a RIFF/WAVE file into the same AiffData representation the AIFF reader produces. WAVE is
+    little-endian throughout, the fmt chunk carries the channel/rate/depth fields, and the sample
+    rate is a plain integer rather than AIFF's 80-bit extended float. We dispatch over the chunks the
+    same way read_aiff does, seeking past anything we don't recognize. */
+
+    let mut file = File::open(filepath)?;
+
+    let mut riff_chunk = [0u8; 4];  // RIFF container tag
+    file.read_exact(&mut riff_chunk)?;
+    if &riff_chunk != b"RIFF" {
+        return Err("Not a valid WAV file".into());
+    }
+
+    let file_size = file.read_u32::<LittleEndian>()? + 8;
+
+    let mut wave_id = [0u8; 4];  // WAVE form type
+    file.read_exact(&mut wave_id)?;
+    if &wave_id != b"WAVE" {
+        return Err("Not a valid WAV file".into());
+    }
+
+    let mut num_channels: u16 = 0;
+    let mut sample_rate_hz: u32 = 0;
+    let mut bit_depth: u16 = 0;
+    let mut audio_data: Vec<u8> = Vec::new();
+
+    loop {
+        let mut chunk_id = [0u8; 4];  // 4-byte ID followed by a little-endian u32 size
+        match file.read_exact(&mut chunk_id) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let chunk_size = file.read_u32::<LittleEndian>()?;
+
+        match &chunk_id {
+            b"fmt " => {
+                let _audio_format = file.read_u16::<LittleEndian>()?;  // 1 == integer PCM
+                num_channels = file.read_u16::<LittleEndian>()?;
+                sample_rate_hz = file.read_u32::<LittleEndian>()?;
+                let _byte_rate = file.read_u32::<LittleEndian>()?;
+                let _block_align = file.read_u16::<LittleEndian>()?;
+                bit_depth = file.read_u16::<LittleEndian>()?;
+                if chunk_size > 16 {
+                    file.seek(SeekFrom::Current((chunk_size - 16) as i64))?;  // skip any extended fmt bytes
+                }
+            }
+            b"data" => {
+                audio_data = vec![0u8; chunk_size as usize];
+                file.read_exact(&mut audio_data)?;
+            }
+            _ => {
+                file.seek(SeekFrom::Current(chunk_size as i64))?;  // unknown chunk, seek past it
+            }
+        }
+
+        if chunk_size % 2 == 1 {
+            file.seek(SeekFrom::Current(1))?;  // chunks are padded to an even byte boundary
+        }
+    }
+
+    if bit_depth == 0 {
+        return Err("fmt chunk not found".into());
+    }
+
+    let bytes_per_sample = bit_depth as usize / 8;
+
+    let mut left_channel_audio = Vec::new();
+    let mut right_channel_audio = Vec::new();
+    let mut interleaved_audio = Vec::new();
+
+    if num_channels == 1 {
+        left_channel_audio = audio_data.chunks(bytes_per_sample)
+            .filter(|chunk| chunk.len() == bytes_per_sample)
+            .map(unpack_sample_le)
+            .collect();
+
+        right_channel_audio = left_channel_audio.clone();
+        interleaved_audio = left_channel_audio.clone();
+    } else if num_channels == 2 {
+        for chunk in audio_data.chunks(bytes_per_sample * 2) {
+            if chunk.len() == bytes_per_sample * 2 {
+                let left_sample_f32 = unpack_sample_le(&chunk[..bytes_per_sample]);
+                let right_sample_f32 = unpack_sample_le(&chunk[bytes_per_sample..]);
+
+                left_channel_audio.push(left_sample_f32);
+                right_channel_audio.push(right_sample_f32);
+                interleaved_audio.push(left_sample_f32);
+                interleaved_audio.push(right_sample_f32);
+            }
+        }
+    } else {
+        return Err("Must have either 1 or 2 audio channels".into());
+    }
+
+    let num_sample_frames = left_channel_audio.len() as u32;
+    let track_length_s = if sample_rate_hz > 0 { (num_sample_frames / sample_rate_hz) as u16 } else { 0 };
+
+    Ok(AiffData {
+        file_size_bytes: file_size,
+        num_channels,
+        num_sample_frames,
+        bit_depth,
+        sample_rate_hz,
+        track_name: String::new(),
+        track_length_s,
+        sound_offset_bytes: 0,
+        sound_block_size_bytes: 0,
+        left_channel_audio,
+        right_channel_audio,
+        interleaved_audio,
+        markers: Vec::new(),
+        instrument: None,
+        comments: Vec::new(),
+    })
+}
+
+pub fn write_wav(filepath: &str, aiff_data: &AiffData) -> Result<(), Box<dyn std::error::Error>> {
+    /* Writes an AiffData out as a little-endian PCM RIFF/WAVE file with the fmt and data chunks. The
+    f32 channels are re-interleaved into little-endian integer samples at the struct's bit depth. */
+
+    let bytes_per_sample = aiff_data.bit_depth as usize / 8;
+
+    let mut sample_data = Vec::with_capacity(aiff_data.interleaved_audio.len() * bytes_per_sample);
+    for &sample in &aiff_data.interleaved_audio {
+        pack_sample_le(sample, bytes_per_sample, &mut sample_data);
+    }
+
+    let block_align = (aiff_data.num_channels as usize * bytes_per_sample) as u16;
+    let byte_rate = aiff_data.sample_rate_hz * block_align as u32;
+    let data_size = sample_data.len() as u32;
+    let riff_size = 4 + (8 + 16) + (8 + data_size);  // WAVE tag + fmt chunk + data chunk
+
+    let mut file = File::create(filepath)?;
+
+    file.write_all(b"RIFF")?;
+    file.write_u32::<LittleEndian>(riff_size)?;
+    file.write_all(b"WAVE")?;
+
+    file.write_all(b"fmt ")?;
+    file.write_u32::<LittleEndian>(16)?;
+    file.write_u16::<LittleEndian>(1)?;  // 1 == integer PCM
+    file.write_u16::<LittleEndian>(aiff_data.num_channels)?;
+    file.write_u32::<LittleEndian>(aiff_data.sample_rate_hz)?;
+    file.write_u32::<LittleEndian>(byte_rate)?;
+    file.write_u16::<LittleEndian>(block_align)?;
+    file.write_u16::<LittleEndian>(aiff_data.bit_depth)?;
+
+    file.write_all(b"data")?;
+    file.write_u32::<LittleEndian>(data_size)?;
+    file.write_all(&sample_data)?;
+
+    Ok(())
+}
+
+pub fn aiff_to_wav(input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /* Reads an AIFF file and writes it back out as a WAV file. */
+    let aiff_data = read_aiff(input)?;
+    write_wav(output, &aiff_data)
+}
+
+pub fn wav_to_aiff(input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /* Reads a WAV file and writes it back out as an AIFF file. */
+    let aiff_data = read_wav(input)?;
+    write_aiff(output, &aiff_data)
+}
+
+fn unpack_sample_le(bytes: &[u8]) -> f32 {
+    /* Little-endian counterpart to read::unpack_sample, converting one WAV PCM sample into the
+    -1.0..1.0 f32 range. 8-bit WAV PCM is unsigned (biased by 128); the wider depths are signed. */
+    match bytes.len() {
+        1 => ((bytes[0] as i32 - 128) as f32) / 128.0,  // 8-bit unsigned
+        2 => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32) / MAX_F32_SIZE,  // 16-bit signed
+        3 => {
+            let raw = (bytes[0] as i32) | ((bytes[1] as i32) << 8) | ((bytes[2] as i32) << 16);
+            let sample = (raw << 8) >> 8;  // sign-extend the 24th bit
+            (sample as f32) / 8388608.0
+        }
+        4 => (i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32) / 2147483648.0,  // 32-bit signed
+        _ => 0.0,
+    }
+}
+
+fn pack_sample_le(sample: f32, bytes_per_sample: usize, out: &mut Vec<u8>) {
+    /* Inverse of unpack_sample_le: scales a -1.0..1.0 f32 back to a little-endian WAV PCM sample. */
+    let clamped = sample.clamp(-1.0, 1.0);
+    match bytes_per_sample {
+        1 => out.push((((clamped * 128.0) as i32).clamp(-128, 127) + 128) as u8),  // 8-bit unsigned
+        2 => {
+            let value = ((clamped * MAX_F32_SIZE) as i32).clamp(-32768, 32767) as i16;
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        3 => {
+            let value = ((clamped * 8388608.0) as i32).clamp(-8388608, 8388607);
+            let bytes = value.to_le_bytes();  // take the low 3 bytes of the little-endian i32
+            out.extend_from_slice(&bytes[0..3]);
+        }
+        4 => {
+            let value = (clamped as f64 * 2147483648.0).clamp(-2147483648.0, 2147483647.0) as i32;
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,94 @@
+use std::fs::File;
+use std::io::Write;
+use byteorder::{BigEndian, WriteBytesExt};
+use crate::read::AiffData;
+
+const MAX_F32_SIZE: f32 = 32768.0;
+
+pub fn write_aiff(filepath: &str, aiff_data: &AiffData) -> Result<(), Box<dyn std::error::Error>> {
+    /* Serializes an AiffData back out to a valid FORM/AIFF file. We re-interleave the f32 channels
+    into big-endian PCM at the struct's bit depth, then emit the FORM container with COMM and SSND
+    chunks, filling in the chunk sizes and the SSND offset/block-size. This is the inverse of
+    read_aiff and makes the crate a round-trip codec. */
+
+    let bytes_per_sample = aiff_data.bit_depth as usize / 8;
+
+    let mut sample_data = Vec::with_capacity(aiff_data.interleaved_audio.len() * bytes_per_sample);  // the raw PCM payload for the SSND chunk
+    for &sample in &aiff_data.interleaved_audio {
+        pack_sample(sample, bytes_per_sample, &mut sample_data);
+    }
+
+    let ssnd_chunk_size = 8 + aiff_data.sound_offset_bytes + sample_data.len() as u32;  // offset (4) + block size (4) + offset padding + samples
+    let form_size = 4 + (8 + 18) + (8 + ssnd_chunk_size);  // AIFF tag + COMM chunk + SSND chunk
+
+    let mut file = File::create(filepath)?;
+
+    file.write_all(b"FORM")?;
+    file.write_u32::<BigEndian>(form_size)?;
+    file.write_all(b"AIFF")?;
+
+    file.write_all(b"COMM")?;  // the common chunk is always 18 bytes
+    file.write_u32::<BigEndian>(18)?;
+    file.write_u16::<BigEndian>(aiff_data.num_channels)?;
+    file.write_u32::<BigEndian>(aiff_data.num_sample_frames)?;
+    file.write_u16::<BigEndian>(aiff_data.bit_depth)?;
+    file.write_all(&write_extended_float(aiff_data.sample_rate_hz as f64))?;
+
+    file.write_all(b"SSND")?;
+    file.write_u32::<BigEndian>(ssnd_chunk_size)?;
+    file.write_u32::<BigEndian>(aiff_data.sound_offset_bytes)?;  // offset
+    file.write_u32::<BigEndian>(aiff_data.sound_block_size_bytes)?;  // block size
+    file.write_all(&vec![0u8; aiff_data.sound_offset_bytes as usize])?;  // the offset counts bytes between the header and the first sample
+    file.write_all(&sample_data)?;
+
+    Ok(())
+}
+
+fn pack_sample(sample: f32, bytes_per_sample: usize, out: &mut Vec<u8>) {
+    /* Inverse of unpack_sample: scales a -1.0..1.0 f32 back up to a signed big-endian integer of
+    the requested width, clamping so values right at the rails don't wrap around. */
+    let clamped = sample.clamp(-1.0, 1.0);
+    match bytes_per_sample {
+        1 => out.push(((clamped * 128.0) as i32).clamp(-128, 127) as i8 as u8),  // 8-bit signed
+        2 => {
+            let value = ((clamped * MAX_F32_SIZE) as i32).clamp(-32768, 32767) as i16;
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        3 => {
+            let value = ((clamped * 8388608.0) as i32).clamp(-8388608, 8388607);
+            let bytes = value.to_be_bytes();  // take the low 3 bytes of the big-endian i32
+            out.extend_from_slice(&bytes[1..4]);
+        }
+        4 => {
+            let value = (clamped as f64 * 2147483648.0).clamp(-2147483648.0, 2147483647.0) as i32;
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        _ => {}
+    }
+}
+
+fn write_extended_float(rate: f64) -> [u8; 10] {
+    /* Inverse of read_extended_float: encodes a sample rate as an IEEE 754 80-bit extended float.
+    We split the rate into a sign, a biased 15-bit exponent (unbiased + 16383) and a 64-bit mantissa
+    whose top bit is the explicit integer bit, then emit the 10 bytes big-endian. Zero is special
+    cased to all-zero bytes since log2(0) is undefined. */
+    let mut buffer = [0u8; 10];
+    if rate == 0.0 {
+        return buffer;
+    }
+
+    let sign = if rate < 0.0 { 0x8000u16 } else { 0 };
+    let magnitude = rate.abs();
+
+    let exponent = magnitude.log2().floor() as i32;  // unbiased exponent
+    let biased_exponent = (exponent + 16383) as u16 | sign;
+    let mantissa = (magnitude / 2f64.powi(exponent) * 2f64.powi(63)) as u64;  // explicit-integer-bit 64-bit mantissa
+
+    buffer[0] = (biased_exponent >> 8) as u8;
+    buffer[1] = (biased_exponent & 0xFF) as u8;
+    for i in 0..8 {
+        buffer[2 + i] = (mantissa >> (56 - i * 8)) as u8;
+    }
+
+    buffer
+}